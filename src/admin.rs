@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+
+use axum::{
+    Router,
+    extract::{Json, Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+use crate::configuration::User;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateUserRequest {
+    pub login: String,
+    pub description: String,
+    #[serde(rename = "userInfo", default)]
+    pub user_info: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateUserRequest {
+    pub description: String,
+    #[serde(rename = "userInfo", default)]
+    pub user_info: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminError {
+    pub error: String,
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    (status, Json(AdminError { error: message })).into_response()
+}
+
+/// Reject the request unless its `Authorization` header matches the
+/// configured admin token exactly. An empty `admin_token` means the admin
+/// API is disabled, so every request is rejected regardless of what (if
+/// anything) is presented.
+fn authorize(headers: &HeaderMap, admin_token: &str) -> Result<(), Response> {
+    let provided = headers.get("authorization").and_then(|h| h.to_str().ok());
+    if admin_token.is_empty() || provided != Some(admin_token) {
+        return Err(error_response(
+            StatusCode::UNAUTHORIZED,
+            "Invalid or missing admin token".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Routes for the `/admin/users` subsystem.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/users", get(list_users).post(create_user))
+        .route(
+            "/admin/users/{login}",
+            get(get_user).put(update_user).delete(delete_user),
+        )
+}
+
+async fn list_users(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Err(response) = authorize(&headers, &state.admin_token) {
+        return response;
+    }
+
+    let users = state.users.read().await;
+    (StatusCode::OK, Json(users.all())).into_response()
+}
+
+async fn get_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(login): Path<String>,
+) -> Response {
+    if let Err(response) = authorize(&headers, &state.admin_token) {
+        return response;
+    }
+
+    let users = state.users.read().await;
+    match users.find(&login) {
+        Some(user) => (StatusCode::OK, Json(user.clone())).into_response(),
+        None => error_response(StatusCode::NOT_FOUND, format!("User {} not found", login)),
+    }
+}
+
+async fn create_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateUserRequest>,
+) -> Response {
+    if let Err(response) = authorize(&headers, &state.admin_token) {
+        return response;
+    }
+
+    let mut users = state.users.write().await;
+    if users.contains_login(&request.login) {
+        return error_response(
+            StatusCode::CONFLICT,
+            format!("User {} already exists", request.login),
+        );
+    }
+
+    let user = User {
+        login: request.login,
+        description: request.description,
+        user_info: request.user_info,
+    };
+    users.insert(user.clone());
+    (StatusCode::CREATED, Json(user)).into_response()
+}
+
+async fn update_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(login): Path<String>,
+    Json(request): Json<UpdateUserRequest>,
+) -> Response {
+    if let Err(response) = authorize(&headers, &state.admin_token) {
+        return response;
+    }
+
+    let mut users = state.users.write().await;
+    if !users.contains_login(&login) {
+        return error_response(StatusCode::NOT_FOUND, format!("User {} not found", login));
+    }
+
+    let user = User {
+        login,
+        description: request.description,
+        user_info: request.user_info,
+    };
+    users.insert(user.clone());
+    (StatusCode::OK, Json(user)).into_response()
+}
+
+async fn delete_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(login): Path<String>,
+) -> Response {
+    if let Err(response) = authorize(&headers, &state.admin_token) {
+        return response;
+    }
+
+    let mut users = state.users.write().await;
+    match users.remove(&login) {
+        Some(_) => StatusCode::NO_CONTENT.into_response(),
+        None => error_response(StatusCode::NOT_FOUND, format!("User {} not found", login)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    use crate::configuration::RegisteredUsers;
+    use crate::oidc::SigningKeys;
+    use crate::templates::Templates;
+    use crate::tokens::TokenStore;
+
+    fn admin_header(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_str(token).unwrap());
+        headers
+    }
+
+    fn test_user(login: &str) -> User {
+        User {
+            login: login.to_string(),
+            description: "Test user".to_string(),
+            user_info: HashMap::new(),
+        }
+    }
+
+    fn test_state(admin_token: &str, users: Vec<User>) -> AppState {
+        AppState {
+            tokens: Arc::new(TokenStore::new()),
+            users: Arc::new(RwLock::new(RegisteredUsers::new(&users))),
+            authorization_header_prefix: "Bearer".to_string(),
+            templates: Arc::new(Templates::load()),
+            signing_keys: Arc::new(SigningKeys::generate()),
+            issuer: "http://localhost:3000".to_string(),
+            admin_token: admin_token.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn authorize_rejects_missing_and_wrong_token() {
+        assert!(authorize(&HeaderMap::new(), "secret").is_err());
+        assert!(authorize(&admin_header("wrong"), "secret").is_err());
+        assert!(authorize(&admin_header("secret"), "secret").is_ok());
+    }
+
+    #[tokio::test]
+    async fn authorize_rejects_everything_when_admin_token_is_empty() {
+        assert!(authorize(&HeaderMap::new(), "").is_err());
+        assert!(authorize(&admin_header(""), "").is_err());
+    }
+
+    #[tokio::test]
+    async fn list_users_requires_admin_token() {
+        let state = test_state("secret", vec![test_user("Admin")]);
+        let response = list_users(State(state), HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn create_user_then_get_and_reject_duplicate() {
+        let state = test_state("secret", vec![]);
+        let headers = admin_header("secret");
+
+        let create_response = create_user(
+            State(state.clone()),
+            headers.clone(),
+            Json(CreateUserRequest {
+                login: "Alice".to_string(),
+                description: "A user".to_string(),
+                user_info: HashMap::new(),
+            }),
+        )
+        .await;
+        assert_eq!(create_response.status(), StatusCode::CREATED);
+
+        let get_response =
+            get_user(State(state.clone()), headers.clone(), Path("Alice".to_string())).await;
+        assert_eq!(get_response.status(), StatusCode::OK);
+
+        let duplicate_response = create_user(
+            State(state),
+            headers,
+            Json(CreateUserRequest {
+                login: "Alice".to_string(),
+                description: "Another user".to_string(),
+                user_info: HashMap::new(),
+            }),
+        )
+        .await;
+        assert_eq!(duplicate_response.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn get_user_404s_for_unknown_login() {
+        let state = test_state("secret", vec![]);
+        let response = get_user(
+            State(state),
+            admin_header("secret"),
+            Path("Missing".to_string()),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn update_user_changes_description_and_404s_for_unknown_login() {
+        let state = test_state("secret", vec![test_user("Alice")]);
+        let headers = admin_header("secret");
+
+        let response = update_user(
+            State(state.clone()),
+            headers.clone(),
+            Path("Alice".to_string()),
+            Json(UpdateUserRequest {
+                description: "Updated".to_string(),
+                user_info: HashMap::new(),
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            state.users.read().await.find(&"Alice".to_string()).unwrap().description,
+            "Updated"
+        );
+
+        let missing_response = update_user(
+            State(state),
+            headers,
+            Path("Missing".to_string()),
+            Json(UpdateUserRequest {
+                description: "Updated".to_string(),
+                user_info: HashMap::new(),
+            }),
+        )
+        .await;
+        assert_eq!(missing_response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn delete_user_removes_user_and_404s_on_second_delete() {
+        let state = test_state("secret", vec![test_user("Alice")]);
+        let headers = admin_header("secret");
+
+        let response =
+            delete_user(State(state.clone()), headers.clone(), Path("Alice".to_string())).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let second_response = delete_user(State(state), headers, Path("Alice".to_string())).await;
+        assert_eq!(second_response.status(), StatusCode::NOT_FOUND);
+    }
+}
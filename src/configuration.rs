@@ -18,13 +18,22 @@ pub struct ServerConfiguration {
     pub port: u16,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdminConfiguration {
+    /// Value callers must send in the `Authorization` header to use
+    /// `/admin/users`. An empty string (the default for configs predating
+    /// this field) disables the admin API entirely.
+    #[serde(default)]
+    pub token: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegisteredUsers {
     /// Keys are logins, values are users
     users: HashMap<String, User>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct User {
     pub login: String,
     pub description: String,
@@ -32,11 +41,24 @@ pub struct User {
     pub user_info: HashMap<String, String>,
 }
 
+impl User {
+    /// Stable subject identifier for OIDC `sub` claims: the user's configured
+    /// `id` userInfo value if present, otherwise their login.
+    pub fn subject(&self) -> String {
+        self.user_info
+            .get("id")
+            .cloned()
+            .unwrap_or_else(|| self.login.clone())
+    }
+}
+
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApplicationConfiguration {
     pub server: ServerConfiguration,
     pub oauth2: OAuth2Configuration,
+    #[serde(default)]
+    pub admin: AdminConfiguration,
     pub users: Vec<User>,
 }
 
@@ -48,6 +70,11 @@ const DEFAULT_CONFIG: &str = include_str!("../config/application.json");
 pub enum ConfigurationError {
     FileNotFound(String),
     CantBuildAbsolutePath(String),
+    /// File extension isn't valid UTF-8; holds the file path. Other unknown
+    /// extensions are parsed as JSON rather than rejected.
+    UnsupportedFormat(String),
+    /// `(file path, underlying parser message)`
+    ParseError(String, String),
 }
 
 impl std::fmt::Display for ConfigurationError {
@@ -57,6 +84,12 @@ impl std::fmt::Display for ConfigurationError {
             &ConfigurationError::CantBuildAbsolutePath(ref path) => {
                 write!(f, "Cant build absolute path: {}", path)
             }
+            ConfigurationError::UnsupportedFormat(extension) => {
+                write!(f, "Unsupported configuration format: {}", extension)
+            }
+            ConfigurationError::ParseError(path, msg) => {
+                write!(f, "Failed to parse configuration file {}: {}", path, msg)
+            }
         }
     }
 }
@@ -71,13 +104,6 @@ impl RegisteredUsers {
         }
     }
 
-    /// Return logins of all users
-    ///
-    /// Make clone of user's keys.
-    pub fn logins(&self) -> Vec<String> {
-        self.users.keys().into_iter().cloned().collect()
-    }
-
     pub fn contains_login(&self, login: &String) -> bool {
         self.users.contains_key(login)
     }
@@ -87,15 +113,19 @@ impl RegisteredUsers {
         self.users.get(login)
     }
 
-    /// Find user by login. Panic if user not found
-    /// Use this method only if you are sure that user exists
-    pub fn load(&self, login: &String) -> &User {
-        self.find(login).unwrap()
-    }
-
     pub fn all(&self) -> Vec<User> {
         self.users.values().cloned().collect()
     }
+
+    /// Insert or overwrite a user by login. Returns the previous user, if any.
+    pub fn insert(&mut self, user: User) -> Option<User> {
+        self.users.insert(user.login.clone(), user)
+    }
+
+    /// Remove a user by login. Returns the removed user, if any.
+    pub fn remove(&mut self, login: &String) -> Option<User> {
+        self.users.remove(login)
+    }
 }
 
 impl ApplicationConfiguration {
@@ -110,6 +140,63 @@ impl ApplicationConfiguration {
         }
     }
 
+    /// Create an application configuration from a TOML string
+    fn from_toml(toml: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match toml::from_str::<ApplicationConfiguration>(toml) {
+            Ok(config) => Ok(config),
+            Err(e) => {
+                warn!("Failed to parse TOML configuration: {}", e);
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    /// Create an application configuration from a YAML string
+    fn from_yaml(yaml: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match serde_yaml::from_str::<ApplicationConfiguration>(yaml) {
+            Ok(config) => Ok(config),
+            Err(e) => {
+                warn!("Failed to parse YAML configuration: {}", e);
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    /// Parse `content` using the format implied by `path`'s extension:
+    /// `.json` via serde_json, `.toml` via toml, `.yaml`/`.yml` via serde_yaml,
+    /// falling back to JSON for any other (or missing) extension. An extension
+    /// that isn't valid UTF-8 is rejected with `UnsupportedFormat`.
+    fn from_content<P: AsRef<Path>>(
+        path: P,
+        content: &str,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let extension = match path.as_ref().extension() {
+            Some(extension) => match extension.to_str() {
+                Some(extension) => extension.to_lowercase(),
+                None => {
+                    return Err(Box::new(ConfigurationError::UnsupportedFormat(
+                        path.as_ref().display().to_string(),
+                    )));
+                }
+            },
+            None => "json".to_string(),
+        };
+
+        let result = match extension.as_str() {
+            "json" => Self::from_json(content),
+            "toml" => Self::from_toml(content),
+            "yaml" | "yml" => Self::from_yaml(content),
+            _ => Self::from_json(content),
+        };
+
+        result.map_err(|e| {
+            Box::new(ConfigurationError::ParseError(
+                path.as_ref().display().to_string(),
+                e.to_string(),
+            )) as Box<dyn std::error::Error>
+        })
+    }
+
     /// Load a user configuration from a file
     pub fn from_file<P: AsRef<Path>>(
         file_name: P,
@@ -135,7 +222,7 @@ impl ApplicationConfiguration {
         let config_content = fs::read_to_string(config_path.clone())
             .map_err(|_| ConfigurationError::FileNotFound(config_path.display().to_string()))?;
 
-        let user_config = Self::from_json(&config_content)?;
+        let user_config = Self::from_content(&config_path, &config_content)?;
 
         info!(
             "Loaded application configuration from file: {}",
@@ -210,6 +297,110 @@ mod tests {
         )
     }
 
+    const TOML_CONFIG: &str = r#"
+        [server]
+        host = "127.0.0.1"
+        port = 8080
+
+        [oauth2]
+        authorization_header_prefix = "Bearer"
+
+        [admin]
+        token = "secret"
+
+        [[users]]
+        login = "Admin"
+        description = "Administrator of system"
+
+        [users.userInfo]
+        id = "1"
+    "#;
+
+    const YAML_CONFIG: &str = r#"
+        server:
+          host: 127.0.0.1
+          port: 8080
+        oauth2:
+          authorization_header_prefix: Bearer
+        admin:
+          token: secret
+        users:
+          - login: Admin
+            description: Administrator of system
+            userInfo:
+              id: "1"
+    "#;
+
+    #[test]
+    fn parses_toml_config() {
+        let config = ApplicationConfiguration::from_toml(TOML_CONFIG).unwrap();
+
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.admin.token, "secret");
+        assert_eq!(config.users.len(), 1);
+        assert_eq!(config.users[0].login, "Admin");
+    }
+
+    #[test]
+    fn parses_yaml_config() {
+        let config = ApplicationConfiguration::from_yaml(YAML_CONFIG).unwrap();
+
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.admin.token, "secret");
+        assert_eq!(config.users.len(), 1);
+        assert_eq!(config.users[0].login, "Admin");
+    }
+
+    #[test]
+    fn from_content_dispatches_by_extension() {
+        assert_eq!(
+            ApplicationConfiguration::from_content("application.toml", TOML_CONFIG)
+                .unwrap()
+                .admin
+                .token,
+            "secret"
+        );
+        assert_eq!(
+            ApplicationConfiguration::from_content("application.yaml", YAML_CONFIG)
+                .unwrap()
+                .admin
+                .token,
+            "secret"
+        );
+        assert_eq!(
+            ApplicationConfiguration::from_content("application.yml", YAML_CONFIG)
+                .unwrap()
+                .admin
+                .token,
+            "secret"
+        );
+        assert_eq!(
+            ApplicationConfiguration::from_content("application.json", DEFAULT_CONFIG)
+                .unwrap()
+                .users
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn from_content_falls_back_to_json_for_unknown_extension() {
+        let config =
+            ApplicationConfiguration::from_content("application.conf", DEFAULT_CONFIG).unwrap();
+        assert_eq!(config.users.len(), 2);
+    }
+
+    #[test]
+    fn from_content_reports_parse_error() {
+        let result = ApplicationConfiguration::from_content("application.toml", "not valid toml");
+
+        assert!(matches!(
+            result,
+            Err(e) if e.downcast_ref::<ConfigurationError>()
+                .is_some_and(|e| matches!(e, ConfigurationError::ParseError(_, _)))
+        ));
+    }
+
     #[test]
     fn load_config_from_file() {
         let config = ApplicationConfiguration::from_file("config/users.json").unwrap();
@@ -1,5 +1,8 @@
+mod admin;
 mod configuration;
+mod oidc;
 mod templates;
+mod tokens;
 
 use axum::{
     Router,
@@ -9,18 +12,17 @@ use axum::{
     response::{Html, IntoResponse, Json, Response},
     routing::{get, post},
 };
-use chrono::{Duration, Utc};
-use clap::{Parser, builder::Str};
+use clap::Parser;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
-use uuid::Uuid;
 
 use configuration::*;
+use oidc::{OpenIdConfiguration, SigningKeys};
 use templates::Templates;
+use tokens::{TokenError, TokenStore};
 
 #[derive(Parser, Debug)]
 #[command(name = "oauth2-mock")]
@@ -39,14 +41,16 @@ struct AuthorizationCodeRequest {
     scope: Option<String>,
     state: Option<String>,
     login: Option<String>, // Store the selected user key
+    nonce: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct AccessTokenRequest {
     grant_type: String,
-    code: String,
+    code: Option<String>,
     redirect_uri: Option<String>,
     client_id: Option<String>,
+    refresh_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +59,8 @@ struct AccessTokenResponse {
     pub token_type: String,
     pub expires_in: i64,
     pub refresh_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,104 +68,79 @@ struct AccessTokenError {
     pub error: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct TokenResponse {
-    access_token: String,
-    token_type: String,
-    expires_in: i64,
-    refresh_token: Option<String>,
-    scope: Option<String>,
+#[derive(Debug, Clone, Deserialize)]
+struct IntrospectRequest {
+    token: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct AuthorizationCode {
-    code: String,
-    client_id: String,
-    redirect_uri: String,
+#[derive(Debug, Clone, Serialize)]
+struct IntrospectionResponse {
+    active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     scope: Option<String>,
-    expires_at: chrono::DateTime<Utc>,
-    user: User,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token_type: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct AccessToken {
+impl IntrospectionResponse {
+    fn inactive() -> Self {
+        Self {
+            active: false,
+            client_id: None,
+            scope: None,
+            sub: None,
+            exp: None,
+            token_type: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RevokeRequest {
     token: String,
-    client_id: String,
-    scope: Option<String>,
-    expires_at: chrono::DateTime<Utc>,
-    user_id: String,
-    user_key: Option<String>, // Store the user key for lookup
 }
 
 #[derive(Debug, Clone)]
 struct AppState {
-    /// login -> code
-    authorization_codes: Arc<HashMap<String, String>>,
+    /// authorization codes and access/refresh tokens, minted per request
+    tokens: Arc<TokenStore>,
 
-    /// code -> access_token
-    access_tokens: Arc<HashMap<String, String>>,
-
-    /// access_token -> refresh_token
-    refresh_tokens: Arc<HashMap<String, String>>,
-
-    /// access_token -> user
-    users_info: Arc<HashMap<String, User>>,
-
-    /// users configuration from file
-    users: Arc<RegisteredUsers>,
+    /// users configuration from file, mutable at runtime via `/admin/users`
+    users: Arc<RwLock<RegisteredUsers>>,
 
     authorization_header_prefix: String,
 
     templates: Arc<Templates>,
-}
 
-/// Generates a hash map with UUID as the value for each key
-fn make_uuids_per_key(keys: &Vec<String>) -> HashMap<String, String> {
-    keys.into_iter()
-        .map(|login| {
-            let uuid = Uuid::new_v4().to_string();
-            (login.clone(), uuid)
-        })
-        .collect()
-}
+    /// RSA keypair used to sign id_tokens and publish the JWKS document
+    signing_keys: Arc<SigningKeys>,
+
+    /// base URL used as the `iss` claim and in discovery document URLs
+    issuer: String,
 
-fn link_access_token_with_user(
-    users: &RegisteredUsers,
-    authorization_codes: &HashMap<String, String>,
-    access_tokens: &HashMap<String, String>,
-) -> HashMap<String, User> {
-    authorization_codes
-        .iter()
-        .map(|(login, code)| {
-            let user = users.load(login);
-            let access_token = access_tokens.get(code).unwrap();
-            (access_token.clone(), user.clone())
-        })
-        .collect()
+    /// token callers must present in the `Authorization` header to use `/admin/users`
+    admin_token: String,
 }
 
 impl AppState {
     fn new(app_config: ApplicationConfiguration, templates: Templates) -> Self {
-        let users = RegisteredUsers::new(app_config.users);
-        let authorization_codes = make_uuids_per_key(&users.logins());
-
-        let codes: Vec<String> = authorization_codes
-            .values()
-            .map(|s| s.to_string())
-            .collect();
-        let access_tokens = make_uuids_per_key(&codes);
-        let refresh_tokens = make_uuids_per_key(&codes);
-
-        let users_info = link_access_token_with_user(&users, &authorization_codes, &access_tokens);
+        let users = RegisteredUsers::new(&app_config.users);
+        let (host, port) = app_config.server_address();
 
         Self {
-            authorization_codes: Arc::new(authorization_codes),
-            access_tokens: Arc::new(access_tokens),
-            refresh_tokens: Arc::new(refresh_tokens),
-            users_info: Arc::new(users_info),
-            users: Arc::new(users),
+            tokens: Arc::new(TokenStore::new()),
+            users: Arc::new(RwLock::new(users)),
             authorization_header_prefix: app_config.oauth2.authorization_header_prefix.clone(),
             templates: Arc::new(templates),
+            signing_keys: Arc::new(SigningKeys::generate()),
+            issuer: format!("http://{}:{}", host, port),
+            admin_token: app_config.admin.token.clone(),
         }
     }
 }
@@ -174,6 +155,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Load user configuration
     let app_config = ApplicationConfiguration::from_file(&args.config)?;
+    let (host, port) = app_config.server_address();
 
     // Load templates
     let templates = Templates::load();
@@ -186,14 +168,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/authorize", get(authorize))
         .route("/access_token", post(access_token))
         .route("/user_info", get(userinfo))
+        .route(
+            "/.well-known/openid-configuration",
+            get(openid_configuration),
+        )
+        .route("/jwks.json", get(jwks))
+        .route("/introspect", post(introspect))
+        .route("/revoke", post(revoke))
+        .merge(admin::router())
         .with_state(state);
 
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
-    info!("OAuth2 Mock Server listening on http://127.0.0.1:3000");
+    let bind_address = format!("{}:{}", host, port);
+    let listener = tokio::net::TcpListener::bind(&bind_address).await?;
+    info!("OAuth2 Mock Server listening on http://{}", bind_address);
     info!("Available endpoints:");
     info!("  - GET  /authorize - Authorization endpoint");
     info!("  - POST /access_token - Token endpoint");
     info!("  - GET  /user_info - User info endpoint");
+    info!("  - POST /introspect - Token introspection endpoint");
+    info!("  - POST /revoke - Token revocation endpoint");
 
     axum::serve(listener, app).await?;
     Ok(())
@@ -201,11 +194,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 async fn home(
     State(state): State<AppState>,
-    Query(params): Query<AuthorizationCodeRequest>,
+    Query(_params): Query<AuthorizationCodeRequest>,
 ) -> Result<Html<String>, StatusCode> {
     let templates = &state.templates;
+    let users = state.users.read().await;
 
-    let html = templates.render_home(state.users.as_ref(), &params);
+    let html = templates.render_home(&users);
 
     Ok(Html(html))
 }
@@ -270,20 +264,35 @@ async fn authorize(
     }
 
     let login = params.login.unwrap();
-    if !state.users.contains_login(&login) {
-        let redirect_uri = format!("{}?error=access_denied", redirect_uri);
-        let msg = format!("User {} not found", login);
-        warn!(msg);
-        return response_302
-            .header("Location", redirect_uri)
-            .body(Body::from(msg))
-            .unwrap();
-    }
+    let user = {
+        let users = state.users.read().await;
+        match users.find(&login) {
+            Some(user) => user.clone(),
+            None => {
+                let redirect_uri = format!("{}?error=access_denied", redirect_uri);
+                let msg = format!("User {} not found", login);
+                warn!(msg);
+                return response_302
+                    .header("Location", redirect_uri)
+                    .body(Body::from(msg))
+                    .unwrap();
+            }
+        }
+    };
 
-    let code = state.authorization_codes.get(&login).unwrap();
+    let code = state
+        .tokens
+        .issue_authorization_code(
+            params.client_id,
+            redirect_uri,
+            params.scope,
+            params.nonce,
+            user,
+        )
+        .await;
     parsed_redirect_uri
         .query_pairs_mut()
-        .append_pair("code", &code);
+        .append_pair("code", &code.code);
 
     if let Some(state) = params.state {
         parsed_redirect_uri
@@ -313,30 +322,129 @@ async fn access_token(
 ) -> Response {
     info!("Token request: {:?}", token_request);
 
-    if token_request.grant_type.as_str() != "authorization_code" {
-        return access_token_error("unsupported_grant_type");
+    match token_request.grant_type.as_str() {
+        "authorization_code" => authorization_code_grant(state, token_request).await,
+        "refresh_token" => refresh_token_grant(state, token_request).await,
+        _ => access_token_error("unsupported_grant_type"),
     }
+}
 
-    // Handle authorization code flow
-    let code = token_request.code;
+async fn authorization_code_grant(state: AppState, token_request: AccessTokenRequest) -> Response {
+    let code = match token_request.code {
+        Some(code) => code,
+        None => return access_token_error("invalid_request"),
+    };
 
-    if !state.access_tokens.contains_key(&code) {
-        info!("Authorization code not found: {}", code);
-        return access_token_error("invalid_grant");
-    }
+    let authorization_code = match state.tokens.consume_authorization_code(&code).await {
+        Ok(authorization_code) => authorization_code,
+        Err(TokenError::InvalidGrant) => {
+            info!("Authorization code not found or expired: {}", code);
+            return access_token_error("invalid_grant");
+        }
+    };
+
+    let wants_id_token = authorization_code
+        .scope
+        .as_deref()
+        .is_some_and(|scope| scope.split_whitespace().any(|s| s == "openid"));
+
+    let id_token = if wants_id_token {
+        match state.signing_keys.issue_id_token(
+            &state.issuer,
+            &authorization_code.client_id,
+            authorization_code.user.subject(),
+            authorization_code.user.user_info.clone(),
+            authorization_code.nonce.clone(),
+        ) {
+            Ok(id_token) => Some(id_token),
+            Err(e) => {
+                warn!("Failed to sign id_token: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
 
-    let access_token = state.access_tokens.get(&code).unwrap();
-    let refresh_token = state.refresh_tokens.get(&code).unwrap();
+    let issued = state
+        .tokens
+        .issue_tokens(
+            authorization_code.client_id,
+            authorization_code.scope,
+            authorization_code.user,
+        )
+        .await;
 
     let body = AccessTokenResponse {
-        access_token: access_token.clone(),
+        access_token: issued.access_token.token,
         token_type: "bearer".to_string(),
-        expires_in: 3600,
-        refresh_token: refresh_token.clone(),
+        expires_in: tokens::ACCESS_TOKEN_TTL_SECONDS,
+        refresh_token: issued.refresh_token.token,
+        id_token,
     };
     return (StatusCode::OK, Json(body)).into_response();
 }
 
+async fn refresh_token_grant(state: AppState, token_request: AccessTokenRequest) -> Response {
+    let refresh_token = match token_request.refresh_token {
+        Some(refresh_token) => refresh_token,
+        None => return access_token_error("invalid_request"),
+    };
+
+    let issued = match state.tokens.redeem_refresh_token(&refresh_token).await {
+        Ok(issued) => issued,
+        Err(TokenError::InvalidGrant) => {
+            info!("Refresh token not found or expired");
+            return access_token_error("invalid_grant");
+        }
+    };
+
+    let body = AccessTokenResponse {
+        access_token: issued.access_token.token,
+        token_type: "bearer".to_string(),
+        expires_in: tokens::ACCESS_TOKEN_TTL_SECONDS,
+        refresh_token: issued.refresh_token.token,
+        id_token: None,
+    };
+    (StatusCode::OK, Json(body)).into_response()
+}
+
+/// OIDC discovery document: `GET /.well-known/openid-configuration`
+async fn openid_configuration(State(state): State<AppState>) -> Response {
+    let config = OpenIdConfiguration::for_issuer(&state.issuer);
+    (StatusCode::OK, Json(config)).into_response()
+}
+
+/// JWKS document: `GET /jwks.json`
+async fn jwks(State(state): State<AppState>) -> Response {
+    (StatusCode::OK, Json(state.signing_keys.jwks())).into_response()
+}
+
+/// RFC 7662 token introspection: `POST /introspect`
+async fn introspect(
+    State(state): State<AppState>,
+    Form(request): Form<IntrospectRequest>,
+) -> Response {
+    let body = match state.tokens.introspect(&request.token).await {
+        Some(info) => IntrospectionResponse {
+            active: true,
+            client_id: Some(info.client_id),
+            scope: info.scope,
+            sub: Some(info.sub),
+            exp: Some(info.exp),
+            token_type: Some(info.token_type.to_string()),
+        },
+        None => IntrospectionResponse::inactive(),
+    };
+    (StatusCode::OK, Json(body)).into_response()
+}
+
+/// RFC 7009 token revocation: `POST /revoke`
+async fn revoke(State(state): State<AppState>, Form(request): Form<RevokeRequest>) -> Response {
+    state.tokens.revoke(&request.token).await;
+    StatusCode::OK.into_response()
+}
+
 async fn userinfo(State(state): State<AppState>, headers: HeaderMap) -> Response {
     let header_prefix = format!("{} ",&state.authorization_header_prefix).to_string();
 
@@ -366,11 +474,13 @@ async fn userinfo(State(state): State<AppState>, headers: HeaderMap) -> Response
 
     info!("User info request for token: {}", token);
 
-    if !state.users_info.contains_key(token) {
-        info!("Invalid token: {}", token);
-        return (StatusCode::UNAUTHORIZED, "Invalid token").into_response();
-    }
+    let access_token = match state.tokens.find_access_token(token).await {
+        Some(access_token) => access_token,
+        None => {
+            info!("Invalid or expired token: {}", token);
+            return (StatusCode::UNAUTHORIZED, "Invalid token").into_response();
+        }
+    };
 
-    let user_info = &state.users_info.get(token).unwrap().user_info;
-    return (StatusCode::OK, Json(user_info)).into_response();
+    return (StatusCode::OK, Json(access_token.user.user_info)).into_response();
 }
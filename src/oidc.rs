@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use rand::rngs::OsRng;
+use rsa::pkcs1::EncodeRsaPrivateKey;
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPrivateKey;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// How long a signed id_token stays valid after it is issued
+pub const ID_TOKEN_TTL_SECONDS: i64 = 3600;
+
+const RSA_KEY_BITS: usize = 2048;
+
+/// Claim names owned by `IdTokenClaims` itself; any same-named entry in a
+/// user's `userInfo` map is dropped before flattening so it can't shadow the
+/// signed claim.
+const RESERVED_CLAIMS: &[&str] = &["iss", "sub", "aud", "iat", "exp", "nonce"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonWebKey {
+    pub kty: &'static str,
+    pub n: String,
+    pub e: String,
+    pub kid: String,
+    pub alg: &'static str,
+    #[serde(rename = "use")]
+    pub use_: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonWebKeySet {
+    pub keys: Vec<JsonWebKey>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenIdConfiguration {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+    pub jwks_uri: String,
+}
+
+impl OpenIdConfiguration {
+    pub fn for_issuer(issuer: &str) -> Self {
+        Self {
+            issuer: issuer.to_string(),
+            authorization_endpoint: format!("{}/authorize", issuer),
+            token_endpoint: format!("{}/access_token", issuer),
+            userinfo_endpoint: format!("{}/user_info", issuer),
+            jwks_uri: format!("{}/jwks.json", issuer),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    aud: String,
+    iat: i64,
+    exp: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nonce: Option<String>,
+    #[serde(flatten)]
+    user_info: HashMap<String, String>,
+}
+
+/// RSA keypair generated at startup, used to sign id_tokens and to publish
+/// the JWKS document so client libraries can verify them.
+pub struct SigningKeys {
+    encoding_key: EncodingKey,
+    kid: String,
+    jwk: JsonWebKey,
+}
+
+impl std::fmt::Debug for SigningKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigningKeys").field("kid", &self.kid).finish()
+    }
+}
+
+impl SigningKeys {
+    pub fn generate() -> Self {
+        let private_key =
+            RsaPrivateKey::new(&mut OsRng, RSA_KEY_BITS).expect("failed to generate RSA key pair");
+        let public_key = private_key.to_public_key();
+
+        let pem = private_key
+            .to_pkcs1_pem(rsa::pkcs1::LineEnding::LF)
+            .expect("failed to encode RSA private key");
+        let encoding_key =
+            EncodingKey::from_rsa_pem(pem.as_bytes()).expect("failed to load RSA encoding key");
+
+        let n = URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be());
+        let e = URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be());
+        let kid: String = Sha256::digest(public_key.n().to_bytes_be())[..8]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+
+        let jwk = JsonWebKey {
+            kty: "RSA",
+            n,
+            e,
+            kid: kid.clone(),
+            alg: "RS256",
+            use_: "sig",
+        };
+
+        Self {
+            encoding_key,
+            kid,
+            jwk,
+        }
+    }
+
+    pub fn jwks(&self) -> JsonWebKeySet {
+        JsonWebKeySet {
+            keys: vec![self.jwk.clone()],
+        }
+    }
+
+    /// Sign an RS256 id_token, echoing `nonce` back if the client sent one.
+    pub fn issue_id_token(
+        &self,
+        issuer: &str,
+        client_id: &str,
+        subject: String,
+        user_info: HashMap<String, String>,
+        nonce: Option<String>,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let mut user_info = user_info;
+        user_info.retain(|key, _| !RESERVED_CLAIMS.contains(&key.as_str()));
+
+        let now = Utc::now();
+        let claims = IdTokenClaims {
+            iss: issuer.to_string(),
+            sub: subject,
+            aud: client_id.to_string(),
+            iat: now.timestamp(),
+            exp: (now + Duration::seconds(ID_TOKEN_TTL_SECONDS)).timestamp(),
+            nonce,
+            user_info,
+        };
+
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(self.kid.clone());
+
+        jsonwebtoken::encode(&header, &claims, &self.encoding_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{DecodingKey, Validation, decode};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct DecodedClaims {
+        iss: String,
+        sub: String,
+        aud: String,
+        #[allow(dead_code)]
+        iat: i64,
+        #[allow(dead_code)]
+        exp: i64,
+        #[serde(flatten)]
+        user_info: HashMap<String, String>,
+    }
+
+    fn decode_with_published_jwk(keys: &SigningKeys, token: &str, client_id: &str) -> DecodedClaims {
+        let jwk = &keys.jwks().keys[0];
+        let decoding_key = DecodingKey::from_rsa_components(&jwk.n, &jwk.e).unwrap();
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[client_id]);
+
+        decode::<DecodedClaims>(token, &decoding_key, &validation)
+            .unwrap()
+            .claims
+    }
+
+    #[test]
+    fn issues_id_token_that_verifies_against_published_jwk() {
+        let keys = SigningKeys::generate();
+        let mut user_info = HashMap::new();
+        user_info.insert("email".to_string(), "alice@example.com".to_string());
+
+        let token = keys
+            .issue_id_token(
+                "https://issuer.example.com",
+                "client-1",
+                "alice".to_string(),
+                user_info,
+                Some("nonce-1".to_string()),
+            )
+            .unwrap();
+
+        let claims = decode_with_published_jwk(&keys, &token, "client-1");
+        assert_eq!(claims.iss, "https://issuer.example.com");
+        assert_eq!(claims.sub, "alice");
+        assert_eq!(claims.aud, "client-1");
+        assert_eq!(claims.user_info.get("email").unwrap(), "alice@example.com");
+    }
+
+    #[test]
+    fn strips_reserved_claim_names_from_user_info() {
+        let keys = SigningKeys::generate();
+        let mut user_info = HashMap::new();
+        user_info.insert("aud".to_string(), "attacker-client".to_string());
+        user_info.insert("sub".to_string(), "attacker".to_string());
+        user_info.insert("role".to_string(), "user".to_string());
+
+        let token = keys
+            .issue_id_token(
+                "https://issuer.example.com",
+                "client-1",
+                "alice".to_string(),
+                user_info,
+                None,
+            )
+            .unwrap();
+
+        let claims = decode_with_published_jwk(&keys, &token, "client-1");
+        assert_eq!(claims.sub, "alice");
+        assert_eq!(claims.aud, "client-1");
+        assert_eq!(claims.user_info.get("role").unwrap(), "user");
+        assert!(!claims.user_info.contains_key("aud"));
+        assert!(!claims.user_info.contains_key("sub"));
+    }
+}
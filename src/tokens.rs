@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::configuration::User;
+
+/// How long an authorization code stays valid before it must be exchanged
+pub const AUTHORIZATION_CODE_TTL_SECONDS: i64 = 60;
+
+/// How long an access token stays valid after it is issued
+pub const ACCESS_TOKEN_TTL_SECONDS: i64 = 3600;
+
+/// How long a refresh token stays valid after it is issued
+pub const REFRESH_TOKEN_TTL_SECONDS: i64 = 86400;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthorizationCode {
+    pub code: String,
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scope: Option<String>,
+    /// Echoed back in the id_token if the client requested one
+    pub nonce: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub user: User,
+}
+
+impl AuthorizationCode {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessToken {
+    pub token: String,
+    pub client_id: String,
+    pub scope: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub user: User,
+}
+
+impl AccessToken {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefreshToken {
+    pub token: String,
+    pub client_id: String,
+    pub scope: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub user: User,
+}
+
+impl RefreshToken {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+}
+
+/// Issued tokens as a pair, returned together from `TokenStore::issue_tokens`
+/// and `TokenStore::redeem_refresh_token`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssuedTokens {
+    pub access_token: AccessToken,
+    pub refresh_token: RefreshToken,
+}
+
+/// Metadata about a live token, as returned by RFC 7662 introspection
+pub struct TokenInfo {
+    pub client_id: String,
+    pub scope: Option<String>,
+    pub sub: String,
+    pub exp: i64,
+    pub token_type: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenError {
+    /// Code/token is unknown, expired, or already consumed
+    InvalidGrant,
+}
+
+/// In-memory store for authorization codes and access/refresh tokens.
+///
+/// Every map is behind its own lock so the `/authorize`, `/access_token` and
+/// `/user_info` handlers mutate and read the stores concurrently without
+/// blocking each other's maps.
+#[derive(Debug, Default)]
+pub struct TokenStore {
+    authorization_codes: RwLock<HashMap<String, AuthorizationCode>>,
+    access_tokens: RwLock<HashMap<String, AccessToken>>,
+    refresh_tokens: RwLock<HashMap<String, RefreshToken>>,
+}
+
+impl TokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mint a fresh, single-use authorization code bound to the request's
+    /// client_id, redirect_uri and scope.
+    pub async fn issue_authorization_code(
+        &self,
+        client_id: String,
+        redirect_uri: String,
+        scope: Option<String>,
+        nonce: Option<String>,
+        user: User,
+    ) -> AuthorizationCode {
+        let code = AuthorizationCode {
+            code: Uuid::new_v4().to_string(),
+            client_id,
+            redirect_uri,
+            scope,
+            nonce,
+            expires_at: Utc::now() + Duration::seconds(AUTHORIZATION_CODE_TTL_SECONDS),
+            user,
+        };
+
+        self.authorization_codes
+            .write()
+            .await
+            .insert(code.code.clone(), code.clone());
+
+        code
+    }
+
+    /// Look up an authorization code and remove it so it can't be replayed.
+    /// Rejects unknown or expired codes with `TokenError::InvalidGrant`.
+    pub async fn consume_authorization_code(
+        &self,
+        code: &str,
+    ) -> Result<AuthorizationCode, TokenError> {
+        let mut codes = self.authorization_codes.write().await;
+        let authorization_code = codes.remove(code).ok_or(TokenError::InvalidGrant)?;
+
+        if authorization_code.is_expired() {
+            return Err(TokenError::InvalidGrant);
+        }
+
+        Ok(authorization_code)
+    }
+
+    /// Mint a fresh access/refresh token pair bound to the given client and user.
+    pub async fn issue_tokens(
+        &self,
+        client_id: String,
+        scope: Option<String>,
+        user: User,
+    ) -> IssuedTokens {
+        let now = Utc::now();
+
+        let access_token = AccessToken {
+            token: Uuid::new_v4().to_string(),
+            client_id: client_id.clone(),
+            scope: scope.clone(),
+            expires_at: now + Duration::seconds(ACCESS_TOKEN_TTL_SECONDS),
+            user: user.clone(),
+        };
+
+        let refresh_token = RefreshToken {
+            token: Uuid::new_v4().to_string(),
+            client_id,
+            scope,
+            expires_at: now + Duration::seconds(REFRESH_TOKEN_TTL_SECONDS),
+            user,
+        };
+
+        self.access_tokens
+            .write()
+            .await
+            .insert(access_token.token.clone(), access_token.clone());
+        self.refresh_tokens
+            .write()
+            .await
+            .insert(refresh_token.token.clone(), refresh_token.clone());
+
+        IssuedTokens {
+            access_token,
+            refresh_token,
+        }
+    }
+
+    /// Exchange a live refresh token for a fresh access/refresh token pair,
+    /// bound to the same client and user. The old refresh token is consumed
+    /// (rotated) so it can't be replayed.
+    pub async fn redeem_refresh_token(&self, refresh_token: &str) -> Result<IssuedTokens, TokenError> {
+        let existing = {
+            let mut refresh_tokens = self.refresh_tokens.write().await;
+            refresh_tokens.remove(refresh_token).ok_or(TokenError::InvalidGrant)?
+        };
+
+        if existing.is_expired() {
+            return Err(TokenError::InvalidGrant);
+        }
+
+        Ok(self
+            .issue_tokens(existing.client_id, existing.scope, existing.user)
+            .await)
+    }
+
+    /// Look up a live (unexpired) access token.
+    pub async fn find_access_token(&self, token: &str) -> Option<AccessToken> {
+        let access_tokens = self.access_tokens.read().await;
+        let access_token = access_tokens.get(token)?;
+
+        if access_token.is_expired() {
+            return None;
+        }
+
+        Some(access_token.clone())
+    }
+
+    /// Describe a token for RFC 7662 introspection. Checks access tokens
+    /// first, then refresh tokens; returns `None` if the token is unknown,
+    /// expired, or was revoked.
+    pub async fn introspect(&self, token: &str) -> Option<TokenInfo> {
+        if let Some(access_token) = self.find_access_token(token).await {
+            return Some(TokenInfo {
+                client_id: access_token.client_id,
+                scope: access_token.scope,
+                sub: access_token.user.subject(),
+                exp: access_token.expires_at.timestamp(),
+                token_type: "access_token",
+            });
+        }
+
+        let refresh_tokens = self.refresh_tokens.read().await;
+        let refresh_token = refresh_tokens.get(token)?;
+        if refresh_token.is_expired() {
+            return None;
+        }
+
+        Some(TokenInfo {
+            client_id: refresh_token.client_id.clone(),
+            scope: refresh_token.scope.clone(),
+            sub: refresh_token.user.subject(),
+            exp: refresh_token.expires_at.timestamp(),
+            token_type: "refresh_token",
+        })
+    }
+
+    /// Remove a token from both the access and refresh token stores
+    /// (RFC 7009 revocation). Revoking an unknown token is a no-op.
+    pub async fn revoke(&self, token: &str) {
+        self.access_tokens.write().await.remove(token);
+        self.refresh_tokens.write().await.remove(token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_user() -> User {
+        User {
+            login: "Admin".to_string(),
+            description: "Administrator of system".to_string(),
+            user_info: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn issues_single_use_authorization_code() {
+        let store = TokenStore::new();
+        let code = store
+            .issue_authorization_code(
+                "client-1".to_string(),
+                "https://example.com/callback".to_string(),
+                Some("openid".to_string()),
+                None,
+                test_user(),
+            )
+            .await;
+
+        let consumed = store.consume_authorization_code(&code.code).await.unwrap();
+        assert_eq!(consumed.client_id, "client-1");
+
+        let result = store.consume_authorization_code(&code.code).await;
+        assert_eq!(result, Err(TokenError::InvalidGrant));
+    }
+
+    #[tokio::test]
+    async fn rejects_expired_authorization_code() {
+        let store = TokenStore::new();
+        let mut code = store
+            .issue_authorization_code(
+                "client-1".to_string(),
+                "https://example.com/callback".to_string(),
+                None,
+                None,
+                test_user(),
+            )
+            .await;
+        code.expires_at = Utc::now() - Duration::seconds(1);
+        store
+            .authorization_codes
+            .write()
+            .await
+            .insert(code.code.clone(), code.clone());
+
+        let result = store.consume_authorization_code(&code.code).await;
+        assert_eq!(result, Err(TokenError::InvalidGrant));
+    }
+
+    #[tokio::test]
+    async fn find_access_token_rejects_expired() {
+        let store = TokenStore::new();
+        let issued = store
+            .issue_tokens("client-1".to_string(), None, test_user())
+            .await;
+
+        let mut expired = issued.access_token.clone();
+        expired.expires_at = Utc::now() - Duration::seconds(1);
+        store
+            .access_tokens
+            .write()
+            .await
+            .insert(expired.token.clone(), expired.clone());
+
+        assert!(store.find_access_token(&expired.token).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn redeems_and_rotates_refresh_token() {
+        let store = TokenStore::new();
+        let issued = store
+            .issue_tokens("client-1".to_string(), None, test_user())
+            .await;
+
+        let refreshed = store
+            .redeem_refresh_token(&issued.refresh_token.token)
+            .await
+            .unwrap();
+        assert_ne!(refreshed.access_token.token, issued.access_token.token);
+        assert_ne!(refreshed.refresh_token.token, issued.refresh_token.token);
+
+        let result = store.redeem_refresh_token(&issued.refresh_token.token).await;
+        assert_eq!(result, Err(TokenError::InvalidGrant));
+    }
+
+    #[tokio::test]
+    async fn revoked_token_is_inactive_on_introspection() {
+        let store = TokenStore::new();
+        let issued = store
+            .issue_tokens("client-1".to_string(), None, test_user())
+            .await;
+
+        assert!(store.introspect(&issued.access_token.token).await.is_some());
+
+        store.revoke(&issued.access_token.token).await;
+
+        assert!(store.introspect(&issued.access_token.token).await.is_none());
+        assert!(store.find_access_token(&issued.access_token.token).await.is_none());
+    }
+}